@@ -1,10 +1,16 @@
 use clap::{Parser, ValueEnum};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
-use std::fs;
+use regex::RegexSet;
+use serde::Serialize;
+use terminal_size::terminal_size;
+use unicode_width::UnicodeWidthStr;
+use std::collections::HashSet;
+use std::fs::{self, Metadata};
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use walkdir::WalkDir;
 
@@ -22,6 +28,52 @@ struct Cli {
     /// Limit output to top N items
     #[arg(short = 'n', long = "limit")]
     limit: Option<usize>,
+
+    /// Report apparent size (metadata length) instead of actual disk allocation
+    #[arg(long)]
+    apparent_size: bool,
+
+    /// Count every hard link separately instead of charging shared inodes once
+    #[arg(long)]
+    count_hard_links: bool,
+
+    /// Stay on one filesystem, skipping directories on other mount points
+    #[arg(short = 'x', long)]
+    one_file_system: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+
+    /// List sampled paths that were skipped due to errors
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Render a nested tree with proportional usage bars instead of a flat table
+    #[arg(long)]
+    tree: bool,
+
+    /// Limit tree depth (implies --tree); defaults to 2 levels
+    #[arg(long)]
+    depth: Option<usize>,
+
+    /// Only count paths matching this pattern (repeatable)
+    #[arg(long)]
+    filter: Vec<String>,
+
+    /// Skip paths matching this pattern, pruning directory descent (repeatable)
+    #[arg(long)]
+    exclude: Vec<String>,
+
+    /// Treat --filter/--exclude patterns as regular expressions instead of globs
+    #[arg(long)]
+    regex: bool,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Human,
+    Json,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -37,6 +89,123 @@ struct ItemStat {
     is_dir: bool,
 }
 
+/// A node in the hierarchical view built for `--tree`. Unlike [`ItemStat`],
+/// which collapses each top-level entry to a single total, this retains the
+/// directory structure so nested usage can be rendered.
+struct TreeNode {
+    path: PathBuf,
+    size: u64,
+    is_dir: bool,
+    children: Vec<TreeNode>,
+}
+
+/// A compiled set of glob or regex patterns.
+enum PatternSet {
+    Glob(GlobSet),
+    Regex(RegexSet),
+}
+
+impl PatternSet {
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            // Match the full path, and also just the file name so `*.log` works
+            // without a leading `**/`.
+            PatternSet::Glob(set) => {
+                set.is_match(path)
+                    || path
+                        .file_name()
+                        .map(|n| set.is_match(Path::new(n)))
+                        .unwrap_or(false)
+            }
+            PatternSet::Regex(set) => set.is_match(&path.to_string_lossy()),
+        }
+    }
+}
+
+/// Include/exclude patterns that scope a scan.
+struct Filters {
+    include: Option<PatternSet>,
+    exclude: Option<PatternSet>,
+}
+
+impl Filters {
+    fn from_cli(cli: &Cli) -> io::Result<Filters> {
+        Ok(Filters {
+            include: compile_patterns(&cli.filter, cli.regex)?,
+            exclude: compile_patterns(&cli.exclude, cli.regex)?,
+        })
+    }
+
+    /// Whether a directory or file should be skipped entirely.
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude.as_ref().is_some_and(|set| set.matches(path))
+    }
+
+    /// Whether a file's bytes should be counted (true when no include set).
+    fn is_included(&self, path: &Path) -> bool {
+        match &self.include {
+            Some(set) => set.matches(path),
+            None => true,
+        }
+    }
+}
+
+/// Compile a list of patterns into a [`PatternSet`], or `None` when empty.
+fn compile_patterns(patterns: &[String], regex: bool) -> io::Result<Option<PatternSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    if regex {
+        let set = RegexSet::new(patterns)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        Ok(Some(PatternSet::Regex(set)))
+    } else {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            builder.add(glob);
+        }
+        let set = builder
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        Ok(Some(PatternSet::Glob(set)))
+    }
+}
+
+/// Accounting for files skipped during a scan, aggregated so the final report
+/// can summarize them instead of interleaving warnings with the progress bar.
+#[derive(Default)]
+struct RuntimeErrors {
+    not_found: u64,
+    permission_denied: u64,
+    other: u64,
+    /// Capped sample of `path: message` lines, shown with `--verbose`.
+    samples: Vec<String>,
+}
+
+impl RuntimeErrors {
+    /// Upper bound on retained sample lines, so a pathological tree can't grow
+    /// this unboundedly.
+    const MAX_SAMPLES: usize = 32;
+
+    fn record(&mut self, path: &Path, err: &io::Error) {
+        match err.kind() {
+            io::ErrorKind::NotFound => self.not_found += 1,
+            io::ErrorKind::PermissionDenied => self.permission_denied += 1,
+            _ => self.other += 1,
+        }
+        if self.samples.len() < Self::MAX_SAMPLES {
+            self.samples
+                .push(format!("{}: {}", path.to_string_lossy(), format_io_error(err)));
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.not_found + self.permission_denied + self.other
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -48,12 +217,65 @@ fn main() {
     pb.enable_steady_tick(Duration::from_millis(100));
     pb.set_message("Scanning...");
 
-    let scan_result = collect_stats(&cli.path, Arc::new(pb.clone()));
+    let filters = match Filters::from_cli(&cli) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Invalid pattern: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let errors = Arc::new(Mutex::new(RuntimeErrors::default()));
+
+    if cli.tree || cli.depth.is_some() {
+        let depth = cli.depth.unwrap_or(2);
+        let root = build_tree(
+            &cli.path,
+            depth,
+            cli.apparent_size,
+            cli.count_hard_links,
+            cli.one_file_system,
+            &filters,
+            &errors,
+            &pb,
+        );
+        pb.finish_and_clear();
+
+        match root {
+            Ok(node) => match cli.output {
+                OutputFormat::Human => print_tree(&node, cli.limit),
+                OutputFormat::Json => print_tree_json(&node, cli.limit),
+            },
+            Err(e) => {
+                eprintln!(
+                    "Failed to scan '{}': {}",
+                    cli.path.to_string_lossy(),
+                    format_io_error(&e)
+                );
+                report_errors(&errors, cli.verbose);
+                std::process::exit(1);
+            }
+        }
+        report_errors(&errors, cli.verbose);
+        return;
+    }
+
+    let scan_result = collect_stats(
+        &cli.path,
+        cli.apparent_size,
+        cli.count_hard_links,
+        cli.one_file_system,
+        &filters,
+        &errors,
+        Arc::new(pb.clone()),
+    );
     pb.finish_and_clear();
 
     let mut items = match scan_result {
         Ok(v) => v,
         Err(e) => {
+            // The scan root itself could not be read; distinguish a missing
+            // path from other failures.
             eprintln!(
                 "Failed to scan '{}': {}",
                 cli.path.to_string_lossy(),
@@ -63,25 +285,54 @@ fn main() {
         }
     };
 
+    report_errors(&errors, cli.verbose);
+
     sort_items(&mut items, cli.sort);
 
+    // Grand total over every item, before `--limit` trims the display set.
+    let total_bytes = items.iter().fold(0u64, |acc, i| acc.saturating_add(i.size));
+
     if let Some(limit) = cli.limit {
         if items.len() > limit {
             items.truncate(limit);
         }
     }
 
-    print_table(&items);
+    match cli.output {
+        OutputFormat::Human => print_table(&items),
+        OutputFormat::Json => print_json(&items, total_bytes),
+    }
 }
 
-fn collect_stats(target: &Path, pb: Arc<ProgressBar>) -> io::Result<Vec<ItemStat>> {
+fn collect_stats(
+    target: &Path,
+    apparent_size: bool,
+    count_hard_links: bool,
+    one_file_system: bool,
+    filters: &Filters,
+    errors: &Arc<Mutex<RuntimeErrors>>,
+    pb: Arc<ProgressBar>,
+) -> io::Result<Vec<ItemStat>> {
     let meta = fs::metadata(target)?;
 
+    // When confining the scan to a single filesystem, remember the root device
+    // so descents onto other mount points can be pruned.
+    let root_dev = if one_file_system {
+        metadata_dev(&meta)
+    } else {
+        None
+    };
+
     if meta.is_file() {
         pb.inc(1);
+        let size = if filters.is_included(target) {
+            file_size(&meta, target, apparent_size)
+        } else {
+            0
+        };
         return Ok(vec![ItemStat {
             path: target.to_path_buf(),
-            size: meta.len(),
+            size,
             is_dir: false,
         }]);
     }
@@ -90,28 +341,31 @@ fn collect_stats(target: &Path, pb: Arc<ProgressBar>) -> io::Result<Vec<ItemStat
         .filter_map(|entry_res| match entry_res {
             Ok(entry) => Some(entry.path()),
             Err(err) => {
-                if !is_permission_denied(&err) {
-                    eprintln!("Warning: could not read an entry in '{}': {}", target.to_string_lossy(), format_io_error(&err));
-                }
+                record_error(errors, target, &err);
                 None
             }
         })
+        // Prune excluded top-level entries outright; `walk_size`'s `filter_entry`
+        // never tests the walk root, so this is the only place they are matched.
+        .filter(|path| !filters.is_excluded(path))
         .collect();
 
     let stats: Vec<ItemStat> = entries
         .into_par_iter()
         .map(|path| {
-            let result = stat_path(&path, &pb);
+            let result = stat_path(
+                &path,
+                apparent_size,
+                count_hard_links,
+                root_dev,
+                filters,
+                errors,
+                &pb,
+            );
             match result {
                 Ok(stat) => Some(stat),
                 Err(err) => {
-                    if !is_permission_denied(&err) {
-                        eprintln!(
-                            "Warning: failed to scan '{}': {}",
-                            path.to_string_lossy(),
-                            format_io_error(&err)
-                        );
-                    }
+                    record_error(errors, &path, &err);
                     None
                 }
             }
@@ -122,18 +376,32 @@ fn collect_stats(target: &Path, pb: Arc<ProgressBar>) -> io::Result<Vec<ItemStat
     Ok(stats)
 }
 
-fn stat_path(path: &Path, pb: &ProgressBar) -> io::Result<ItemStat> {
+#[allow(clippy::too_many_arguments)]
+fn stat_path(
+    path: &Path,
+    apparent_size: bool,
+    count_hard_links: bool,
+    root_dev: Option<u64>,
+    filters: &Filters,
+    errors: &Arc<Mutex<RuntimeErrors>>,
+    pb: &ProgressBar,
+) -> io::Result<ItemStat> {
     let meta = fs::metadata(path)?;
     if meta.is_file() {
         pb.inc(1);
+        let size = if filters.is_included(path) {
+            file_size(&meta, path, apparent_size)
+        } else {
+            0
+        };
         return Ok(ItemStat {
             path: path.to_path_buf(),
-            size: meta.len(),
+            size,
             is_dir: false,
         });
     }
 
-    let size = walk_size(path, pb);
+    let size = walk_size(path, apparent_size, count_hard_links, root_dev, filters, errors, pb);
     Ok(ItemStat {
         path: path.to_path_buf(),
         size,
@@ -141,21 +409,45 @@ fn stat_path(path: &Path, pb: &ProgressBar) -> io::Result<ItemStat> {
     })
 }
 
-fn walk_size(path: &Path, pb: &ProgressBar) -> u64 {
+#[allow(clippy::too_many_arguments)]
+fn walk_size(
+    path: &Path,
+    apparent_size: bool,
+    count_hard_links: bool,
+    root_dev: Option<u64>,
+    filters: &Filters,
+    errors: &Arc<Mutex<RuntimeErrors>>,
+    pb: &ProgressBar,
+) -> u64 {
     let mut total = 0u64;
+    // Inodes already charged within this subtree, so multiple hard links to the
+    // same file are counted once. Each call owns its own set, so hard links
+    // spanning two separately walked subtrees (two top-level entries, or two
+    // collapsed directories of the tree view) are still counted twice.
+    let mut seen: HashSet<(u64, u64)> = HashSet::new();
+
+    let walker = WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            // Exclude patterns never prune the walk root itself, only its contents.
+            if entry.depth() > 0 && filters.is_excluded(entry.path()) {
+                return false;
+            }
+            match root_dev {
+                // Prune descent into anything on a different device (mount point).
+                Some(dev) => dir_entry_dev(entry).map(|d| d == dev).unwrap_or(true),
+                None => true,
+            }
+        });
 
-    for entry in WalkDir::new(path).follow_links(false).into_iter() {
+    for entry in walker {
         let entry = match entry {
             Ok(e) => e,
             Err(err) => {
+                let at = err.path().unwrap_or(path);
                 if let Some(ioe) = err.io_error() {
-                    if !is_permission_denied(ioe) {
-                        eprintln!(
-                            "Warning: traversal issue under '{}': {}",
-                            path.to_string_lossy(),
-                            format_io_error(ioe)
-                        );
-                    }
+                    record_error(errors, at, ioe);
                 }
                 continue;
             }
@@ -163,24 +455,25 @@ fn walk_size(path: &Path, pb: &ProgressBar) -> u64 {
 
         if entry.file_type().is_file() {
             pb.inc(1);
+            if !filters.is_included(entry.path()) {
+                continue;
+            }
             match entry.metadata() {
-                Ok(md) => total = total.saturating_add(md.len()),
-                Err(err) => {
-                    if let Some(ioe) = err.io_error() {
-                        if !is_permission_denied(ioe) {
-                            eprintln!(
-                                "Warning: metadata read failed for '{}': {}",
-                                entry.path().to_string_lossy(),
-                                format_io_error(ioe)
-                            );
+                Ok(md) => {
+                    if !count_hard_links {
+                        if let Some(key) = inode_key(&md) {
+                            if !seen.insert(key) {
+                                continue;
+                            }
                         }
-                    } else {
-                        eprintln!(
-                            "Warning: metadata read failed for '{}': {}",
-                            entry.path().to_string_lossy(),
-                            err
-                        );
                     }
+                    total = total.saturating_add(file_size(&md, entry.path(), apparent_size))
+                }
+                Err(err) => {
+                    let ioe = err
+                        .into_io_error()
+                        .unwrap_or_else(|| io::Error::other("metadata read failed"));
+                    record_error(errors, entry.path(), &ioe);
                 }
             }
         }
@@ -189,6 +482,196 @@ fn walk_size(path: &Path, pb: &ProgressBar) -> u64 {
     total
 }
 
+/// Build a [`TreeNode`] for `target`, expanding directories down to `depth`
+/// levels of children (deeper directories keep their full subtree total but no
+/// expanded children). Shares a single inode set across the expanded levels so
+/// hard links there are charged once, and honors the mount boundary when
+/// `one_file_system`. Each directory past the depth is summed by `walk_size`
+/// with its own inode set, so a hard link reached both inside such a directory
+/// and elsewhere in the tree is counted more than once.
+#[allow(clippy::too_many_arguments)]
+fn build_tree(
+    target: &Path,
+    depth: usize,
+    apparent_size: bool,
+    count_hard_links: bool,
+    one_file_system: bool,
+    filters: &Filters,
+    errors: &Arc<Mutex<RuntimeErrors>>,
+    pb: &ProgressBar,
+) -> io::Result<TreeNode> {
+    let meta = fs::metadata(target)?;
+    let root_dev = if one_file_system {
+        metadata_dev(&meta)
+    } else {
+        None
+    };
+    let mut seen: HashSet<(u64, u64)> = HashSet::new();
+    Ok(build_node(
+        target,
+        &meta,
+        depth,
+        apparent_size,
+        count_hard_links,
+        root_dev,
+        filters,
+        &mut seen,
+        errors,
+        pb,
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_node(
+    path: &Path,
+    meta: &Metadata,
+    depth_remaining: usize,
+    apparent_size: bool,
+    count_hard_links: bool,
+    root_dev: Option<u64>,
+    filters: &Filters,
+    seen: &mut HashSet<(u64, u64)>,
+    errors: &Arc<Mutex<RuntimeErrors>>,
+    pb: &ProgressBar,
+) -> TreeNode {
+    if meta.is_file() {
+        pb.inc(1);
+        let size = if filters.is_included(path) {
+            charge_file(meta, path, apparent_size, count_hard_links, seen)
+        } else {
+            0
+        };
+        return TreeNode {
+            path: path.to_path_buf(),
+            size,
+            is_dir: false,
+            children: Vec::new(),
+        };
+    }
+
+    let read = match fs::read_dir(path) {
+        Ok(r) => r,
+        Err(err) => {
+            record_error(errors, path, &err);
+            return TreeNode {
+                path: path.to_path_buf(),
+                size: 0,
+                is_dir: true,
+                children: Vec::new(),
+            };
+        }
+    };
+
+    let mut children = Vec::new();
+    let mut total = 0u64;
+
+    for entry_res in read {
+        let entry = match entry_res {
+            Ok(e) => e,
+            Err(err) => {
+                record_error(errors, path, &err);
+                continue;
+            }
+        };
+        let child_path = entry.path();
+        // `symlink_metadata` so symlinks are treated as leaves and we never
+        // recurse through them.
+        let child_meta = match fs::symlink_metadata(&child_path) {
+            Ok(m) => m,
+            Err(err) => {
+                record_error(errors, &child_path, &err);
+                continue;
+            }
+        };
+
+        // Excluded paths are pruned entirely, skipping descent.
+        if filters.is_excluded(&child_path) {
+            continue;
+        }
+
+        if child_meta.is_dir() {
+            if let Some(dev) = root_dev {
+                if metadata_dev(&child_meta) != Some(dev) {
+                    continue;
+                }
+            }
+            let child = if depth_remaining > 1 {
+                build_node(
+                    &child_path,
+                    &child_meta,
+                    depth_remaining - 1,
+                    apparent_size,
+                    count_hard_links,
+                    root_dev,
+                    filters,
+                    seen,
+                    errors,
+                    pb,
+                )
+            } else {
+                // Past the display depth: keep the full subtree total but don't
+                // retain its children.
+                let size = walk_size(
+                    &child_path,
+                    apparent_size,
+                    count_hard_links,
+                    root_dev,
+                    filters,
+                    errors,
+                    pb,
+                );
+                TreeNode {
+                    path: child_path,
+                    size,
+                    is_dir: true,
+                    children: Vec::new(),
+                }
+            };
+            total = total.saturating_add(child.size);
+            children.push(child);
+        } else {
+            pb.inc(1);
+            if !filters.is_included(&child_path) {
+                continue;
+            }
+            let size = charge_file(&child_meta, &child_path, apparent_size, count_hard_links, seen);
+            total = total.saturating_add(size);
+            children.push(TreeNode {
+                path: child_path,
+                size,
+                is_dir: false,
+                children: Vec::new(),
+            });
+        }
+    }
+
+    TreeNode {
+        path: path.to_path_buf(),
+        size: total,
+        is_dir: true,
+        children,
+    }
+}
+
+/// Size to charge for a single file, skipping already-seen inodes so hard links
+/// are only counted once (unless `count_hard_links` is set).
+fn charge_file(
+    meta: &Metadata,
+    path: &Path,
+    apparent_size: bool,
+    count_hard_links: bool,
+    seen: &mut HashSet<(u64, u64)>,
+) -> u64 {
+    if !count_hard_links {
+        if let Some(key) = inode_key(meta) {
+            if !seen.insert(key) {
+                return 0;
+            }
+        }
+    }
+    file_size(meta, path, apparent_size)
+}
+
 fn sort_items(items: &mut [ItemStat], sort: SortBy) {
     match sort {
         SortBy::Size => {
@@ -244,6 +727,173 @@ fn print_table(items: &[ItemStat]) {
     }
 }
 
+/// Number of cells in a usage bar.
+const BAR_CELLS: usize = 16;
+
+fn print_tree(root: &TreeNode, limit: Option<usize>) {
+    let width = term_width();
+    print_node(root, 0, root.size, limit, width);
+}
+
+fn print_node(node: &TreeNode, depth: usize, parent_size: u64, limit: Option<usize>, width: usize) {
+    let share = if parent_size > 0 {
+        node.size as f64 / parent_size as f64
+    } else {
+        0.0
+    };
+
+    let indent = "  ".repeat(depth);
+    let size = human_size(node.size);
+    let bar = render_bar(share);
+    let prefix = format!("{indent}{size:>10}  {bar} {:>3.0}%  ", share * 100.0);
+
+    // Root shows its full path; nested nodes show just the entry name.
+    let name = if depth == 0 {
+        node.path.to_string_lossy().into_owned()
+    } else {
+        node.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| node.path.to_string_lossy().into_owned())
+    };
+    let name = if node.is_dir {
+        format!("{name}/")
+    } else {
+        name
+    };
+
+    let room = width.saturating_sub(UnicodeWidthStr::width(prefix.as_str()));
+    println!("{prefix}{}", truncate_to_width(&name, room));
+
+    let mut kids: Vec<&TreeNode> = node.children.iter().collect();
+    kids.sort_by(|a, b| {
+        b.size
+            .cmp(&a.size)
+            .then_with(|| a.path.to_string_lossy().cmp(&b.path.to_string_lossy()))
+    });
+    if let Some(l) = limit {
+        kids.truncate(l);
+    }
+
+    for child in kids {
+        print_node(child, depth + 1, node.size, limit, width);
+    }
+}
+
+/// A proportional bar of [`BAR_CELLS`] cells, filled according to `share`.
+fn render_bar(share: f64) -> String {
+    let share = share.clamp(0.0, 1.0);
+    let filled = (share * BAR_CELLS as f64).round() as usize;
+    let filled = filled.min(BAR_CELLS);
+    let mut bar = String::with_capacity(BAR_CELLS * 3);
+    for _ in 0..filled {
+        bar.push('█');
+    }
+    for _ in filled..BAR_CELLS {
+        bar.push('░');
+    }
+    bar
+}
+
+/// Terminal width in columns, falling back to 80 when it can't be determined.
+fn term_width() -> usize {
+    terminal_size().map(|(w, _)| w.0 as usize).unwrap_or(80)
+}
+
+/// Truncate `s` to at most `max` display columns, appending `…` when cut. Uses
+/// display width so multi-width glyphs are accounted for.
+fn truncate_to_width(s: &str, max: usize) -> String {
+    if UnicodeWidthStr::width(s) <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+
+    let budget = max.saturating_sub(1);
+    let mut out = String::new();
+    let mut used = 0usize;
+    for ch in s.chars() {
+        let w = UnicodeWidthStr::width(ch.to_string().as_str());
+        if used + w > budget {
+            break;
+        }
+        out.push(ch);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+#[derive(Serialize)]
+struct JsonItem {
+    path: String,
+    size_bytes: u64,
+    is_dir: bool,
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+    items: Vec<JsonItem>,
+    total_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct JsonTreeNode {
+    path: String,
+    size_bytes: u64,
+    is_dir: bool,
+    children: Vec<JsonTreeNode>,
+}
+
+fn print_tree_json(root: &TreeNode, limit: Option<usize>) {
+    let report = tree_to_json(root, limit);
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{json}"),
+        Err(err) => eprintln!("Failed to serialize output as JSON: {err}"),
+    }
+}
+
+/// Convert a [`TreeNode`] into its serializable form, applying the same
+/// per-level sort and `--limit` cap as the human tree view.
+fn tree_to_json(node: &TreeNode, limit: Option<usize>) -> JsonTreeNode {
+    let mut kids: Vec<&TreeNode> = node.children.iter().collect();
+    kids.sort_by(|a, b| {
+        b.size
+            .cmp(&a.size)
+            .then_with(|| a.path.to_string_lossy().cmp(&b.path.to_string_lossy()))
+    });
+    if let Some(l) = limit {
+        kids.truncate(l);
+    }
+
+    JsonTreeNode {
+        path: node.path.to_string_lossy().into_owned(),
+        size_bytes: node.size,
+        is_dir: node.is_dir,
+        children: kids.into_iter().map(|c| tree_to_json(c, limit)).collect(),
+    }
+}
+
+fn print_json(items: &[ItemStat], total_bytes: u64) {
+    let report = JsonReport {
+        items: items
+            .iter()
+            .map(|i| JsonItem {
+                path: i.path.to_string_lossy().into_owned(),
+                size_bytes: i.size,
+                is_dir: i.is_dir,
+            })
+            .collect(),
+        total_bytes,
+    };
+
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{json}"),
+        Err(err) => eprintln!("Failed to serialize output as JSON: {err}"),
+    }
+}
+
 fn human_size(bytes: u64) -> String {
     const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
 
@@ -261,8 +911,113 @@ fn human_size(bytes: u64) -> String {
     format!("{value:.2} {}", UNITS[idx])
 }
 
-fn is_permission_denied(err: &io::Error) -> bool {
-    err.kind() == io::ErrorKind::PermissionDenied
+/// Device id for a path's metadata, or `None` where it is unavailable (the
+/// `--one-file-system` boundary is then not enforced).
+#[cfg(unix)]
+fn metadata_dev(meta: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(meta.dev())
+}
+
+#[cfg(not(unix))]
+fn metadata_dev(_meta: &Metadata) -> Option<u64> {
+    None
+}
+
+/// Device id for a directory entry, reading its (unfollowed) metadata.
+#[cfg(unix)]
+fn dir_entry_dev(entry: &walkdir::DirEntry) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    entry.metadata().ok().map(|m| m.dev())
+}
+
+#[cfg(not(unix))]
+fn dir_entry_dev(_entry: &walkdir::DirEntry) -> Option<u64> {
+    None
+}
+
+/// Stable `(device, inode)` identity used to deduplicate hard links. Returns
+/// `None` on platforms without readily available inode numbers, where every
+/// file is simply counted.
+#[cfg(unix)]
+fn inode_key(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn inode_key(_meta: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Size counted for a single file: actual disk allocation by default, or the
+/// apparent metadata length when `apparent_size` is set.
+fn file_size(meta: &Metadata, path: &Path, apparent_size: bool) -> u64 {
+    if apparent_size {
+        meta.len()
+    } else {
+        allocated_size(meta, path)
+    }
+}
+
+/// Bytes actually allocated on disk for a file, as `du` reports them. On Unix
+/// this is the block count rounded to 512-byte units (so sparse files are
+/// undercounted and tiny files block-rounded, matching `du`). On platforms
+/// without a cheap allocation query we fall back to the apparent length.
+#[cfg(unix)]
+fn allocated_size(meta: &Metadata, _path: &Path) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.blocks().saturating_mul(512)
+}
+
+#[cfg(not(unix))]
+fn allocated_size(meta: &Metadata, _path: &Path) -> u64 {
+    meta.len()
+}
+
+/// Record a skipped path into the shared error accounting.
+fn record_error(errors: &Arc<Mutex<RuntimeErrors>>, path: &Path, err: &io::Error) {
+    if let Ok(mut guard) = errors.lock() {
+        guard.record(path, err);
+    }
+}
+
+/// Print a one-line summary of skipped paths after the scan, listing the
+/// sampled paths when `verbose` is set. Nothing is printed on a clean scan.
+fn report_errors(errors: &Arc<Mutex<RuntimeErrors>>, verbose: bool) {
+    let guard = match errors.lock() {
+        Ok(g) => g,
+        Err(_) => return,
+    };
+
+    let total = guard.total();
+    if total == 0 {
+        return;
+    }
+
+    let mut parts = Vec::new();
+    if guard.permission_denied > 0 {
+        parts.push(format!("{} permission denied", guard.permission_denied));
+    }
+    if guard.not_found > 0 {
+        parts.push(format!("{} not found", guard.not_found));
+    }
+    if guard.other > 0 {
+        parts.push(format!("{} unreadable", guard.other));
+    }
+
+    let noun = if total == 1 { "file" } else { "files" };
+    eprintln!("skipped {total} {noun} ({})", parts.join(", "));
+
+    if verbose {
+        for sample in &guard.samples {
+            eprintln!("  {sample}");
+        }
+        let shown = guard.samples.len() as u64;
+        if total > shown {
+            eprintln!("  ... and {} more", total - shown);
+        }
+    }
 }
 
 fn format_io_error(err: &io::Error) -> String {